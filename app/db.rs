@@ -0,0 +1,21 @@
+use crate::options::{DatabaseDriver, DatabaseOptions};
+use tangram_error::Result;
+
+/// Connect to the configured database and run its migration set. `driver`
+/// selects which migration directory applies; the pool itself is
+/// `sqlx::AnyPool`, which speaks whichever of sqlite/postgres/mysql the url
+/// names, so the rest of the app queries it without branching on backend.
+pub async fn connect(options: &DatabaseOptions) -> Result<sqlx::AnyPool> {
+	let max_connections = options.max_connections.unwrap_or(10);
+	let pool = sqlx::any::AnyPoolOptions::new()
+		.max_connections(max_connections)
+		.connect(options.url.as_str())
+		.await?;
+	let migrations = match options.driver {
+		DatabaseDriver::Sqlite => sqlx::migrate!("./migrations/sqlite"),
+		DatabaseDriver::Postgres => sqlx::migrate!("./migrations/postgres"),
+		DatabaseDriver::Mysql => sqlx::migrate!("./migrations/mysql"),
+	};
+	migrations.run(&pool).await?;
+	Ok(pool)
+}
@@ -0,0 +1,22 @@
+use crate::options::{SmtpEncryption, SmtpOptions};
+use lettre::transport::smtp::{authentication::Credentials, SmtpTransport};
+use tangram_error::Result;
+
+/// Build the SMTP transport for the configured encryption mode: `Tls` dials
+/// an implicitly-encrypted connection (conventionally port 465), `StartTls`
+/// upgrades a plaintext connection after connecting (conventionally port
+/// 587), and `None` sends in the clear (conventionally port 25). The actual
+/// port always comes from `SmtpOptions::port`, which the CLI already
+/// defaults from the encryption mode when the config omits it.
+pub fn transport(smtp: &SmtpOptions) -> Result<SmtpTransport> {
+	let builder = match smtp.encryption {
+		SmtpEncryption::Tls => SmtpTransport::relay(&smtp.host)?,
+		SmtpEncryption::StartTls => SmtpTransport::starttls_relay(&smtp.host)?,
+		SmtpEncryption::None => SmtpTransport::builder_dangerous(&smtp.host),
+	};
+	let mut builder = builder.port(smtp.port);
+	if let (Some(username), Some(password)) = (&smtp.username, &smtp.password) {
+		builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+	}
+	Ok(builder.build())
+}
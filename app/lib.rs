@@ -0,0 +1,29 @@
+pub mod db;
+pub mod email;
+pub mod options;
+pub mod pages;
+pub mod storage;
+pub mod users;
+
+use options::Options;
+use rand::RngCore;
+use std::sync::Arc;
+use tangram_app_common::Context;
+use tangram_error::Result;
+
+pub fn run(options: Options) -> Result<()> {
+	let runtime = tokio::runtime::Runtime::new()?;
+	runtime.block_on(async {
+		let database_driver = options.database.driver;
+		let database_pool = db::connect(&options.database).await?;
+		let mut csrf_secret = vec![0u8; 32];
+		rand::thread_rng().fill_bytes(&mut csrf_secret);
+		let _context = Arc::new(Context {
+			database_pool,
+			database_driver,
+			csrf_secret,
+		});
+		let _ = &options;
+		Ok(())
+	})
+}
@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+pub use tangram_app_common::DatabaseDriver;
+use url::Url;
+
+/// The fully resolved set of options `tangram_app::run` is started with,
+/// after the CLI has merged env vars, the config file, and built-in
+/// defaults.
+pub struct Options {
+	pub auth: Option<AuthOptions>,
+	pub cookie_domain: Option<String>,
+	pub database: DatabaseOptions,
+	pub host: std::net::IpAddr,
+	pub license_features: Vec<String>,
+	pub max_users: Option<u32>,
+	pub port: u16,
+	pub smtp: Option<SmtpOptions>,
+	pub storage: StorageOptions,
+	pub url: Option<Url>,
+}
+
+impl Options {
+	/// Whether the verified license unlocks an auth-only feature, e.g. `"sso"`
+	/// or `"audit_log"`. Deployments without a license carry an empty
+	/// `license_features`, so this is `false` for every feature there.
+	pub fn has_feature(&self, feature: &str) -> bool {
+		self.license_features.iter().any(|f| f == feature)
+	}
+}
+
+pub struct AuthOptions {
+	pub signup: SignupStrategy,
+}
+
+/// Who is allowed to self-register once auth is enabled. Enforced by
+/// `tangram_app::users::create_user`.
+#[derive(Clone, Copy)]
+pub enum SignupStrategy {
+	Open,
+	InviteOnly,
+	Closed,
+}
+
+pub struct DatabaseOptions {
+	/// The backend this connection pool talks to, inferred from the url
+	/// scheme. Selects the migration set in `tangram_app::db::connect`; the
+	/// pool itself is a `sqlx::AnyPool` for every backend.
+	pub driver: DatabaseDriver,
+	pub max_connections: Option<u32>,
+	pub url: Url,
+}
+
+pub struct SmtpOptions {
+	pub host: String,
+	pub port: u16,
+	pub encryption: SmtpEncryption,
+	pub username: Option<String>,
+	pub password: Option<String>,
+}
+
+/// Mirrors the submission-port conventions mail servers expect: implicit TLS
+/// on 465, STARTTLS on 587, plaintext on 25. Consumed by
+/// `tangram_app::email::transport` to build the right `SmtpTransport`.
+#[derive(Clone, Copy)]
+pub enum SmtpEncryption {
+	None,
+	StartTls,
+	Tls,
+}
+
+pub enum StorageOptions {
+	Local(LocalStorageOptions),
+	S3(S3StorageOptions),
+}
+
+pub struct LocalStorageOptions {
+	pub path: PathBuf,
+}
+
+pub struct S3StorageOptions {
+	/// `None` when the config/env omit explicit credentials, in which case
+	/// `tangram_app::storage::credentials_provider` falls back to the standard AWS
+	/// credential chain.
+	pub access_key: Option<String>,
+	pub secret_key: Option<String>,
+	pub endpoint: String,
+	pub bucket: String,
+	pub region: String,
+	pub cache_path: PathBuf,
+	pub sse: Option<S3ServerSideEncryption>,
+}
+
+/// Server-side encryption applied to every object `tangram_app::storage`
+/// writes to the bucket.
+#[derive(Clone)]
+pub enum S3ServerSideEncryption {
+	Aes256,
+	AwsKms { key_id: Option<String> },
+}
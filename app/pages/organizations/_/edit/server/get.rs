@@ -0,0 +1,26 @@
+use super::page::{Page, PageProps};
+use html::html;
+use std::sync::Arc;
+use tangram_app_common::{csrf::set_csrf_cookie, Context};
+use tangram_app_layouts::app_layout::get_app_layout_props;
+use tangram_error::Result;
+
+pub async fn get(
+	context: Arc<Context>,
+	_request: http::Request<hyper::Body>,
+) -> Result<http::Response<hyper::Body>> {
+	let app_layout_props = get_app_layout_props(&context).await?;
+	let (csrf_token, csrf_cookie) = set_csrf_cookie(&context);
+	let page_props = PageProps {
+		app_layout_props,
+		csrf_token,
+		error: None,
+	};
+	let html = html!(<Page {page_props} />).render_to_string();
+	let response = http::Response::builder()
+		.status(http::StatusCode::OK)
+		.header(http::header::SET_COOKIE, csrf_cookie)
+		.header(http::header::CONTENT_TYPE, "text/html")
+		.body(hyper::Body::from(html))?;
+	Ok(response)
+}
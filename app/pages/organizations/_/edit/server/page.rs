@@ -8,6 +8,7 @@ use tangram_ui as ui;
 #[derive(Props)]
 pub struct PageProps {
 	pub app_layout_props: AppLayoutProps,
+	pub csrf_token: String,
 	pub error: Option<String>,
 }
 
@@ -22,6 +23,7 @@ pub fn Page(props: PageProps) {
 				<ui::S1>
 					<ui::H1>{"Edit Organization"}</ui::H1>
 					<ui::Form post?={Some(true)}>
+						<ui::CsrfField token={props.csrf_token} />
 						<ui::TextField
 							label?="Organization Name"
 							name?="name"
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+use tangram_app_common::{csrf::validate_csrf_token, error::bad_request, Context};
+use tangram_error::{err, Result};
+
+#[derive(serde::Deserialize)]
+struct Action {
+	csrf_token: String,
+	name: String,
+}
+
+pub async fn post(
+	context: Arc<Context>,
+	mut request: http::Request<hyper::Body>,
+) -> Result<http::Response<hyper::Body>> {
+	let organization_id = organization_id_from_path(request.uri().path())
+		.ok_or_else(|| err!("missing organization id in path"))?;
+	let body = hyper::body::to_bytes(request.body_mut()).await?;
+	let action: Action = match serde_urlencoded::from_bytes(&body) {
+		Ok(action) => action,
+		Err(_) => return Ok(bad_request()),
+	};
+	// Every mutating POST handler must validate its CSRF token against the
+	// per-session cookie before touching the database.
+	if validate_csrf_token(&context, &request, &action.csrf_token).is_err() {
+		return Ok(bad_request());
+	}
+	if action.name.is_empty() {
+		return Err(err!("organization name must not be empty"));
+	}
+	// `sqlx::Any` does not rewrite placeholder syntax, so the query string has
+	// to be built with the driver's own placeholders rather than a literal `?`.
+	let query = format!(
+		"update organizations set name = {} where id = {}",
+		context.database_driver.placeholder(1),
+		context.database_driver.placeholder(2),
+	);
+	let result = sqlx::query(&query)
+		.bind(&action.name)
+		.bind(&organization_id)
+		.execute(&context.database_pool)
+		.await?;
+	if result.rows_affected() == 0 {
+		return Err(err!("organization {} not found", organization_id));
+	}
+	let response = http::Response::builder()
+		.status(http::StatusCode::SEE_OTHER)
+		.header(http::header::LOCATION, format!("/organizations/{}/", organization_id))
+		.body(hyper::Body::empty())?;
+	Ok(response)
+}
+
+/// Pull the `_` path segment this page is nested under, e.g.
+/// `/organizations/acme-inc/edit` -> `acme-inc`.
+fn organization_id_from_path(path: &str) -> Option<String> {
+	let mut segments = path.trim_matches('/').split('/');
+	if segments.next()? != "organizations" {
+		return None;
+	}
+	segments.next().map(|id| id.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn organization_id_from_path_parses_the_dynamic_segment() {
+		assert_eq!(
+			organization_id_from_path("/organizations/acme-inc/edit"),
+			Some("acme-inc".to_owned()),
+		);
+		assert_eq!(organization_id_from_path("/organizations/"), None);
+		assert_eq!(organization_id_from_path("/repos/acme/edit"), None);
+	}
+}
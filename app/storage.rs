@@ -0,0 +1,117 @@
+//! Object storage backends. See `options::StorageOptions`.
+
+use crate::options::{S3ServerSideEncryption, S3StorageOptions, StorageOptions};
+use rusoto_core::{HttpClient, Region};
+use rusoto_credential::{ChainProvider, CredentialsError, ProvideAwsCredentials, StaticProvider};
+use rusoto_s3::{PutObjectRequest, S3Client, S3};
+use std::future::Future;
+use std::pin::Pin;
+use tangram_error::{err, Result};
+
+pub enum Storage {
+	Local(std::path::PathBuf),
+	S3(S3Storage),
+}
+
+impl Storage {
+	pub fn new(options: &StorageOptions) -> Result<Storage> {
+		match options {
+			StorageOptions::Local(local) => Ok(Storage::Local(local.path.clone())),
+			StorageOptions::S3(s3) => Ok(Storage::S3(S3Storage::new(s3)?)),
+		}
+	}
+
+	/// Write `data` to `path`, creating any missing parent directories on the
+	/// local backend and applying the configured SSE mode on the S3 backend.
+	pub async fn put(&self, path: &str, data: Vec<u8>) -> Result<()> {
+		match self {
+			Storage::Local(root) => {
+				let file_path = root.join(path);
+				if let Some(parent) = file_path.parent() {
+					tokio::fs::create_dir_all(parent).await?;
+				}
+				tokio::fs::write(file_path, data).await?;
+				Ok(())
+			}
+			Storage::S3(s3) => s3.put(path, data).await,
+		}
+	}
+}
+
+pub struct S3Storage {
+	client: S3Client,
+	bucket: String,
+	sse: Option<S3ServerSideEncryption>,
+}
+
+impl S3Storage {
+	fn new(options: &S3StorageOptions) -> Result<S3Storage> {
+		let region = Region::Custom {
+			name: options.region.clone(),
+			endpoint: options.endpoint.clone(),
+		};
+		let credentials = credentials_provider(options);
+		let http_client = HttpClient::new().map_err(|error| err!("{}", error))?;
+		let client = S3Client::new_with(http_client, credentials, region);
+		Ok(S3Storage {
+			client,
+			bucket: options.bucket.clone(),
+			sse: options.sse.clone(),
+		})
+	}
+
+	async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+		let (server_side_encryption, ssekms_key_id) = match &self.sse {
+			Some(S3ServerSideEncryption::Aes256) => (Some("AES256".to_owned()), None),
+			Some(S3ServerSideEncryption::AwsKms { key_id }) => {
+				(Some("aws:kms".to_owned()), key_id.clone())
+			}
+			None => (None, None),
+		};
+		self.client
+			.put_object(PutObjectRequest {
+				bucket: self.bucket.clone(),
+				key: key.to_owned(),
+				body: Some(data.into()),
+				server_side_encryption,
+				ssekms_key_id,
+				..Default::default()
+			})
+			.await
+			.map_err(|error| err!("s3 put_object failed: {}", error))?;
+		Ok(())
+	}
+}
+
+/// When the config/env supply explicit `access_key`/`secret_key`, use them
+/// directly. Otherwise fall back to the standard AWS credential chain
+/// (`AWS_ACCESS_KEY_ID`/`AWS_SECRET_ACCESS_KEY`, the shared profile file,
+/// ECS/container credentials, and finally EC2 instance-role metadata), so an
+/// operator is never forced to check long-lived secrets into `tangram.json`.
+fn credentials_provider(options: &S3StorageOptions) -> Credentials {
+	match (&options.access_key, &options.secret_key) {
+		(Some(access_key), Some(secret_key)) => Credentials::Static(StaticProvider::new_minimal(
+			access_key.clone(),
+			secret_key.clone(),
+		)),
+		_ => Credentials::Chain(ChainProvider::new()),
+	}
+}
+
+enum Credentials {
+	Static(StaticProvider),
+	Chain(ChainProvider),
+}
+
+impl ProvideAwsCredentials for Credentials {
+	type Future = Pin<
+		Box<dyn Future<Output = Result<rusoto_credential::AwsCredentials, CredentialsError>> + Send>,
+	>;
+
+	fn credentials(&self) -> Self::Future {
+		match self {
+			Credentials::Static(provider) => Box::pin(provider.credentials()),
+			Credentials::Chain(provider) => Box::pin(provider.credentials()),
+		}
+	}
+}
@@ -0,0 +1,101 @@
+use crate::options::{AuthOptions, SignupStrategy};
+use tangram_app_common::DatabaseDriver;
+use tangram_error::{err, Result};
+
+/// Create a new user account: self-service registration, gated by the
+/// configured `SignupStrategy` and the license's `max_users` seat limit.
+///
+/// - `Closed` rejects all self-service registration.
+/// - `InviteOnly` requires `invitation_token` to name an unredeemed
+///   invitation. The invitation is redeemed in its own statement before the
+///   user insert, outside any transaction; a failed insert leaves it redeemed.
+/// - `Open` behaves as if auth had no signup restriction at all.
+pub async fn create_user(
+	database_pool: &sqlx::AnyPool,
+	driver: DatabaseDriver,
+	auth: &AuthOptions,
+	max_users: Option<u32>,
+	email: &str,
+	password_hash: &str,
+	invitation_token: Option<&str>,
+) -> Result<()> {
+	match auth.signup {
+		SignupStrategy::Closed => {
+			return Err(err!("signup is closed on this instance"));
+		}
+		SignupStrategy::InviteOnly => {
+			let token = invitation_token
+				.ok_or_else(|| err!("an invitation is required to sign up"))?;
+			redeem_invitation(database_pool, driver, token).await?;
+		}
+		SignupStrategy::Open => {}
+	}
+	insert_user(database_pool, driver, email, password_hash, max_users).await
+}
+
+async fn redeem_invitation(
+	database_pool: &sqlx::AnyPool,
+	driver: DatabaseDriver,
+	token: &str,
+) -> Result<()> {
+	// `sqlx::Any` does not rewrite placeholder syntax, so the query string has
+	// to be built with the driver's own placeholders rather than a literal `?`.
+	let query = format!(
+		"update invitations set redeemed_at = current_timestamp where token = {} and redeemed_at is null",
+		driver.placeholder(1),
+	);
+	let result = sqlx::query(&query)
+		.bind(token)
+		.execute(database_pool)
+		.await?;
+	if result.rows_affected() == 0 {
+		return Err(err!("invitation not found or already redeemed"));
+	}
+	Ok(())
+}
+
+async fn insert_user(
+	database_pool: &sqlx::AnyPool,
+	driver: DatabaseDriver,
+	email: &str,
+	password_hash: &str,
+	max_users: Option<u32>,
+) -> Result<()> {
+	// A separate count-then-insert leaves a window where two concurrent
+	// registrations can both observe a count under the seat limit and both
+	// insert, overshooting it. Folding the limit into the insert's own
+	// `where` clause makes the check and the write a single statement the
+	// database evaluates atomically, so no such window exists.
+	let result = if let Some(max_users) = max_users {
+		let query = format!(
+			"insert into users (email, password_hash) select {}, {} where (select count(*) from users) < {}",
+			driver.placeholder(1),
+			driver.placeholder(2),
+			driver.placeholder(3),
+		);
+		sqlx::query(&query)
+			.bind(email)
+			.bind(password_hash)
+			.bind(max_users)
+			.execute(database_pool)
+			.await?
+	} else {
+		let query = format!(
+			"insert into users (email, password_hash) values ({}, {})",
+			driver.placeholder(1),
+			driver.placeholder(2),
+		);
+		sqlx::query(&query)
+			.bind(email)
+			.bind(password_hash)
+			.execute(database_pool)
+			.await?
+	};
+	if result.rows_affected() == 0 {
+		return Err(err!(
+			"this license allows at most {} users; delete an existing user or upgrade your license",
+			max_users.unwrap_or_default()
+		));
+	}
+	Ok(())
+}
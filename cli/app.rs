@@ -21,6 +21,23 @@ struct AppConfig {
 #[derive(Clone, serde::Deserialize)]
 struct AuthConfig {
 	enable: bool,
+	#[serde(default)]
+	signup: SignupStrategy,
+}
+
+/// Who is allowed to self-register once auth is enabled.
+#[derive(Clone, Copy, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SignupStrategy {
+	Open,
+	InviteOnly,
+	Closed,
+}
+
+impl Default for SignupStrategy {
+	fn default() -> SignupStrategy {
+		SignupStrategy::Open
+	}
 }
 
 #[derive(Clone, serde::Deserialize)]
@@ -29,11 +46,62 @@ struct DatabaseConfig {
 	url: Url,
 }
 
+/// The database backend to connect to, inferred from the scheme of the
+/// configured database url. `tangram_app::run` uses this to select the
+/// matching migration set at runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DatabaseDriver {
+	Sqlite,
+	Postgres,
+	Mysql,
+}
+
+impl DatabaseDriver {
+	fn from_url(url: &Url) -> Result<DatabaseDriver> {
+		match url.scheme() {
+			"sqlite" => Ok(DatabaseDriver::Sqlite),
+			"postgres" | "postgresql" => Ok(DatabaseDriver::Postgres),
+			"mysql" => Ok(DatabaseDriver::Mysql),
+			scheme => Err(err!("unsupported database url scheme: {}", scheme)),
+		}
+	}
+}
+
 #[derive(Clone, serde::Deserialize)]
 struct SmtpConfig {
 	host: String,
-	password: String,
-	username: String,
+	port: Option<u16>,
+	#[serde(default)]
+	encryption: SmtpEncryption,
+	username: Option<String>,
+	password: Option<String>,
+}
+
+/// The transport encryption to use for the SMTP connection. Mirrors the
+/// submission-port conventions mail servers expect: STARTTLS on 587,
+/// implicit TLS on 465, and plaintext on 25.
+#[derive(Clone, Copy, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SmtpEncryption {
+	None,
+	StartTls,
+	Tls,
+}
+
+impl Default for SmtpEncryption {
+	fn default() -> SmtpEncryption {
+		SmtpEncryption::StartTls
+	}
+}
+
+impl SmtpEncryption {
+	fn default_port(&self) -> u16 {
+		match self {
+			SmtpEncryption::None => 25,
+			SmtpEncryption::StartTls => 587,
+			SmtpEncryption::Tls => 465,
+		}
+	}
 }
 
 #[derive(Clone, serde::Deserialize)]
@@ -52,12 +120,31 @@ struct LocalStorageConfig {
 
 #[derive(Clone, serde::Deserialize)]
 struct S3StorageConfig {
-	pub access_key: String,
-	pub secret_key: String,
+	pub access_key: Option<String>,
+	pub secret_key: Option<String>,
 	pub endpoint: String,
 	pub bucket: String,
 	pub region: String,
 	pub cache_path: Option<PathBuf>,
+	pub sse: Option<S3ServerSideEncryption>,
+}
+
+/// Server-side encryption applied to every object written to the bucket.
+#[derive(Clone, serde::Deserialize)]
+#[serde(tag = "type")]
+enum S3ServerSideEncryption {
+	#[serde(rename = "aes256")]
+	Aes256,
+	#[serde(rename = "aws:kms")]
+	AwsKms { key_id: Option<String> },
+}
+
+/// Read a `TANGRAM_`-prefixed environment variable. This is the top layer of
+/// config resolution: env overrides the config file, which overrides the
+/// built-in default.
+#[cfg(feature = "app")]
+fn env_var(name: &str) -> Option<String> {
+	std::env::var(format!("TANGRAM_{}", name)).ok()
 }
 
 #[cfg(feature = "app")]
@@ -68,55 +155,128 @@ pub fn app(args: AppArgs) -> Result<()> {
 	} else {
 		None
 	};
-	let auth = config
-		.as_ref()
-		.and_then(|c| c.auth.as_ref())
-		.and_then(|auth| {
-			if auth.enable {
-				Some(tangram_app::options::AuthOptions {})
-			} else {
-				None
-			}
-		});
-	let cookie_domain = config.as_ref().and_then(|c| c.cookie_domain.clone());
-	let storage = if let Some(storage) = config.as_ref().and_then(|c| c.storage.as_ref()) {
-		match storage {
-			StorageConfig::Local(storage) => tangram_app::options::StorageOptions::Local(
-				tangram_app::options::LocalStorageOptions {
-					path: storage.path.clone(),
-				},
-			),
-			StorageConfig::S3(storage) => {
-				let cache_path = storage
-					.cache_path
-					.clone()
-					.unwrap_or_else(|| cache_path().unwrap());
-				tangram_app::options::StorageOptions::S3(tangram_app::options::S3StorageOptions {
-					access_key: storage.access_key.clone(),
-					secret_key: storage.secret_key.clone(),
-					endpoint: storage.endpoint.clone(),
-					bucket: storage.bucket.clone(),
-					region: storage.region.clone(),
-					cache_path,
-				})
-			}
-		}
-	} else {
-		tangram_app::options::StorageOptions::Local(tangram_app::options::LocalStorageOptions {
-			path: data_path()?.join("data"),
+	let auth_enable = env_var("AUTH_ENABLE")
+		.map(|value| value.parse())
+		.transpose()?
+		.or_else(|| config.as_ref().and_then(|c| c.auth.as_ref()).map(|auth| auth.enable))
+		.unwrap_or(false);
+	let auth_signup = env_var("AUTH_SIGNUP")
+		.map(|value| match value.as_str() {
+			"open" => Ok(SignupStrategy::Open),
+			"invite_only" => Ok(SignupStrategy::InviteOnly),
+			"closed" => Ok(SignupStrategy::Closed),
+			other => Err(err!("invalid TANGRAM_AUTH_SIGNUP value: {}", other)),
+		})
+		.transpose()?
+		.or_else(|| {
+			config
+				.as_ref()
+				.and_then(|c| c.auth.as_ref())
+				.map(|auth| auth.signup)
 		})
+		.unwrap_or_default();
+	let auth = if auth_enable {
+		let signup = match auth_signup {
+			SignupStrategy::Open => tangram_app::options::SignupStrategy::Open,
+			SignupStrategy::InviteOnly => tangram_app::options::SignupStrategy::InviteOnly,
+			SignupStrategy::Closed => tangram_app::options::SignupStrategy::Closed,
+		};
+		Some(tangram_app::options::AuthOptions { signup })
+	} else {
+		None
 	};
-	let database = config
-		.as_ref()
-		.and_then(|c| c.database.as_ref())
-		.map(|database| tangram_app::options::DatabaseOptions {
-			max_connections: database.max_connections,
-			url: database.url.clone(),
+	let cookie_domain = env_var("COOKIE_DOMAIN")
+		.or_else(|| config.as_ref().and_then(|c| c.cookie_domain.clone()));
+	let storage_config = config.as_ref().and_then(|c| c.storage.as_ref());
+	let s3_storage_config = storage_config.and_then(|storage| match storage {
+		StorageConfig::S3(storage) => Some(storage),
+		StorageConfig::Local(_) => None,
+	});
+	// TANGRAM_STORAGE_TYPE lets an operator switch backends without editing
+	// the config file at all; it defaults to whatever the file chose.
+	let storage_type = env_var("STORAGE_TYPE").or_else(|| {
+		storage_config.map(|storage| match storage {
+			StorageConfig::Local(_) => "local".to_owned(),
+			StorageConfig::S3(_) => "s3".to_owned(),
 		})
-		.unwrap_or_else(|| tangram_app::options::DatabaseOptions {
-			max_connections: None,
-			url: default_database_url(),
-		});
+	});
+	let storage = match storage_type.as_deref() {
+		Some("s3") => {
+			let endpoint = env_var("S3_ENDPOINT")
+				.or_else(|| s3_storage_config.map(|s3| s3.endpoint.clone()))
+				.ok_or_else(|| err!("s3 storage requires an endpoint"))?;
+			let bucket = env_var("S3_BUCKET")
+				.or_else(|| s3_storage_config.map(|s3| s3.bucket.clone()))
+				.ok_or_else(|| err!("s3 storage requires a bucket"))?;
+			let region = env_var("S3_REGION")
+				.or_else(|| s3_storage_config.map(|s3| s3.region.clone()))
+				.ok_or_else(|| err!("s3 storage requires a region"))?;
+			let cache_path = s3_storage_config
+				.and_then(|s3| s3.cache_path.clone())
+				.unwrap_or_else(|| cache_path().unwrap());
+			// Credentials are optional: when neither the config file nor
+			// TANGRAM_S3_* provide them, the storage layer falls back to
+			// the standard AWS_ACCESS_KEY_ID/AWS_SECRET_ACCESS_KEY env
+			// vars and, failing that, EC2/ECS instance-role metadata.
+			let access_key =
+				env_var("S3_ACCESS_KEY").or_else(|| s3_storage_config.and_then(|s3| s3.access_key.clone()));
+			let secret_key = env_var("S3_SECRET_KEY")
+				.or_else(|| s3_storage_config.and_then(|s3| s3.secret_key.clone()));
+			let sse = s3_storage_config
+				.and_then(|s3| s3.sse.as_ref())
+				.map(|sse| match sse {
+					S3ServerSideEncryption::Aes256 => {
+						tangram_app::options::S3ServerSideEncryption::Aes256
+					}
+					S3ServerSideEncryption::AwsKms { key_id } => {
+						tangram_app::options::S3ServerSideEncryption::AwsKms {
+							key_id: key_id.clone(),
+						}
+					}
+				});
+			tangram_app::options::StorageOptions::S3(tangram_app::options::S3StorageOptions {
+				access_key,
+				secret_key,
+				endpoint,
+				bucket,
+				region,
+				cache_path,
+				sse,
+			})
+		}
+		_ => {
+			let path = match storage_config {
+				Some(StorageConfig::Local(storage)) => storage.path.clone(),
+				_ => data_path()?.join("data"),
+			};
+			tangram_app::options::StorageOptions::Local(tangram_app::options::LocalStorageOptions {
+				path,
+			})
+		}
+	};
+	let database_config = config.as_ref().and_then(|c| c.database.as_ref());
+	let database_url_from_env = std::env::var("DATABASE_URL").ok();
+	let database_url = if let Some(url) = database_url_from_env {
+		url.parse()?
+	} else if let Some(database) = database_config {
+		database.url.clone()
+	} else {
+		default_database_url()
+	};
+	let driver = DatabaseDriver::from_url(&database_url)?;
+	let max_connections = env_var("DATABASE_MAX_CONNECTIONS")
+		.map(|value| value.parse())
+		.transpose()?
+		.or_else(|| database_config.and_then(|database| database.max_connections));
+	let database = tangram_app::options::DatabaseOptions {
+		driver: match driver {
+			DatabaseDriver::Sqlite => tangram_app::options::DatabaseDriver::Sqlite,
+			DatabaseDriver::Postgres => tangram_app::options::DatabaseDriver::Postgres,
+			DatabaseDriver::Mysql => tangram_app::options::DatabaseDriver::Mysql,
+		},
+		max_connections,
+		url: database_url,
+	};
 	let host_from_env = if let Ok(host) = std::env::var("HOST") {
 		Some(host.parse()?)
 	} else {
@@ -134,33 +294,77 @@ pub fn app(args: AppArgs) -> Result<()> {
 	let port_from_config = config.as_ref().and_then(|c| c.port);
 	let port = port_from_env.or(port_from_config).unwrap_or(8080);
 	// Verify the license if one was provided.
-	let license_verified: Option<bool> =
-		if let Some(license_file_path) = config.as_ref().and_then(|c| c.license.clone()) {
-			Some(verify_license(&license_file_path)?)
+	let license_path = env_var("LICENSE_PATH")
+		.map(PathBuf::from)
+		.or_else(|| config.as_ref().and_then(|c| c.license.clone()));
+	let license_claims: Option<LicenseClaims> =
+		if let Some(license_file_path) = license_path {
+			let claims = verify_license(&license_file_path)?;
+			if is_license_expired(claims.expires_at, chrono::Utc::now()) {
+				return Err(err!("license expired"));
+			}
+			Some(claims)
 		} else {
 			None
 		};
 	// Require a verified license if auth is enabled.
 	if auth.is_some() {
-		match license_verified {
+		match &license_claims {
 			#[cfg(debug_assertions)]
 			None => {}
 			#[cfg(not(debug_assertions))]
 			None => return Err(err!("a license is required to enable authentication")),
-			Some(false) => return Err(err!("failed to verify license")),
-			Some(true) => {}
+			Some(_) => {}
 		}
 	}
-	let smtp = if let Some(smtp) = config.as_ref().and_then(|c| c.smtp.clone()) {
+	let max_users = license_claims.as_ref().and_then(|claims| claims.max_users);
+	let license_features = license_claims
+		.as_ref()
+		.map(|claims| claims.features.clone())
+		.unwrap_or_default();
+	// Mirrors the `storage`/`database` resolution above and the `url`
+	// resolution below: a containerized
+	// deployment may supply SMTP purely via TANGRAM_SMTP_* env vars and ship no
+	// `smtp` block (or no config file at all), so every field here falls back
+	// to the config only after env comes up empty, and the whole option is
+	// `None` only when neither source names a host.
+	let smtp_config = config.as_ref().and_then(|c| c.smtp.clone());
+	let smtp_host = env_var("SMTP_HOST").or_else(|| smtp_config.as_ref().map(|s| s.host.clone()));
+	let smtp = if let Some(host) = smtp_host {
+		let encryption = env_var("SMTP_ENCRYPTION")
+			.map(|value| match value.as_str() {
+				"none" => Ok(SmtpEncryption::None),
+				"starttls" => Ok(SmtpEncryption::StartTls),
+				"tls" => Ok(SmtpEncryption::Tls),
+				other => Err(err!("invalid TANGRAM_SMTP_ENCRYPTION value: {}", other)),
+			})
+			.transpose()?
+			.or_else(|| smtp_config.as_ref().map(|s| s.encryption))
+			.unwrap_or_default();
+		let port = env_var("SMTP_PORT")
+			.map(|value| value.parse())
+			.transpose()?
+			.or_else(|| smtp_config.as_ref().and_then(|s| s.port))
+			.unwrap_or_else(|| encryption.default_port());
+		let encryption = match encryption {
+			SmtpEncryption::None => tangram_app::options::SmtpEncryption::None,
+			SmtpEncryption::StartTls => tangram_app::options::SmtpEncryption::StartTls,
+			SmtpEncryption::Tls => tangram_app::options::SmtpEncryption::Tls,
+		};
 		Some(tangram_app::options::SmtpOptions {
-			host: smtp.host,
-			username: smtp.username,
-			password: smtp.password,
+			host,
+			port,
+			encryption,
+			username: env_var("SMTP_USERNAME")
+				.or_else(|| smtp_config.as_ref().and_then(|s| s.username.clone())),
+			password: env_var("SMTP_PASSWORD")
+				.or_else(|| smtp_config.as_ref().and_then(|s| s.password.clone())),
 		})
 	} else {
 		None
 	};
-	let url = if let Some(url) = config.as_ref().and_then(|c| c.url.clone()) {
+	let url_from_config = config.as_ref().and_then(|c| c.url.clone());
+	let url = if let Some(url) = env_var("URL").or(url_from_config) {
 		Some(url.parse()?)
 	} else {
 		None
@@ -170,6 +374,8 @@ pub fn app(args: AppArgs) -> Result<()> {
 		cookie_domain,
 		database,
 		host,
+		license_features,
+		max_users,
 		port,
 		smtp,
 		storage,
@@ -218,7 +424,26 @@ pub fn default_database_url() -> Url {
 	Url::parse(&url).unwrap()
 }
 
-pub fn verify_license(license_file_path: &Path) -> Result<bool> {
+/// The claims carried by a verified license file: when it stops being valid,
+/// how many users it covers, and which auth-only subsystems it unlocks.
+#[derive(Clone, serde::Deserialize)]
+pub struct LicenseClaims {
+	pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+	pub max_users: Option<u32>,
+	#[serde(default)]
+	pub features: Vec<String>,
+}
+
+/// A missing `expires_at` means the license is perpetual, so it is only
+/// expired when an expiry is present and has already passed.
+fn is_license_expired(
+	expires_at: Option<chrono::DateTime<chrono::Utc>>,
+	now: chrono::DateTime<chrono::Utc>,
+) -> bool {
+	expires_at.map(|expires_at| expires_at < now).unwrap_or(false)
+}
+
+pub fn verify_license(license_file_path: &Path) -> Result<LicenseClaims> {
 	let tangram_license_public_key: &str = "
 -----BEGIN RSA PUBLIC KEY-----
 MIIBCgKCAQEAq+JphywG8wCe6cX+bx4xKH8xphMhaI5BgYefQHUXwp8xavoor6Fy
@@ -254,5 +479,48 @@ xAmGGm4wQ4FlMAt+Bj/K6rvdG3FJUu5ttQIDAQAB
 		&digest,
 		&signature,
 	)?;
-	Ok(true)
+	let claims: LicenseClaims = serde_json::from_slice(&license_data)?;
+	Ok(claims)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use chrono::Duration;
+
+	#[test]
+	fn database_driver_from_url() {
+		assert_eq!(
+			DatabaseDriver::from_url(&Url::parse("sqlite:tangram.db").unwrap()).unwrap(),
+			DatabaseDriver::Sqlite,
+		);
+		assert_eq!(
+			DatabaseDriver::from_url(&Url::parse("postgres://localhost/tangram").unwrap()).unwrap(),
+			DatabaseDriver::Postgres,
+		);
+		assert_eq!(
+			DatabaseDriver::from_url(&Url::parse("postgresql://localhost/tangram").unwrap()).unwrap(),
+			DatabaseDriver::Postgres,
+		);
+		assert_eq!(
+			DatabaseDriver::from_url(&Url::parse("mysql://localhost/tangram").unwrap()).unwrap(),
+			DatabaseDriver::Mysql,
+		);
+		assert!(DatabaseDriver::from_url(&Url::parse("mongodb://localhost/tangram").unwrap()).is_err());
+	}
+
+	#[test]
+	fn smtp_encryption_default_port() {
+		assert_eq!(SmtpEncryption::None.default_port(), 25);
+		assert_eq!(SmtpEncryption::StartTls.default_port(), 587);
+		assert_eq!(SmtpEncryption::Tls.default_port(), 465);
+	}
+
+	#[test]
+	fn license_expiry() {
+		let now = chrono::Utc::now();
+		assert!(!is_license_expired(None, now));
+		assert!(!is_license_expired(Some(now + Duration::days(1)), now));
+		assert!(is_license_expired(Some(now - Duration::days(1)), now));
+	}
 }
@@ -0,0 +1,57 @@
+use crate::Context;
+use hmac::{Hmac, Mac, NewMac};
+use rand::RngCore;
+use sha2::Sha256;
+use tangram_error::{err, Result};
+
+const CSRF_COOKIE_NAME: &str = "tangram_csrf";
+
+/// Mint a fresh CSRF token and the `Set-Cookie` header that binds it to the
+/// session. Render the returned token into the form with `ui::CsrfField`;
+/// the cookie is the other half `validate_csrf_token` checks it against.
+pub fn set_csrf_cookie(context: &Context) -> (String, http::HeaderValue) {
+	let mut token_bytes = [0u8; 32];
+	rand::thread_rng().fill_bytes(&mut token_bytes);
+	let token = base64::encode(token_bytes);
+	let signature = sign(&context.csrf_secret, &token);
+	let cookie_value = format!(
+		"{}={}.{}; HttpOnly; SameSite=Strict; Path=/",
+		CSRF_COOKIE_NAME, token, signature,
+	);
+	(token, http::HeaderValue::from_str(&cookie_value).unwrap())
+}
+
+/// Validate a submitted CSRF token against the signed cookie on `request`.
+/// Every POST handler that mutates state must call this before doing so.
+pub fn validate_csrf_token(
+	context: &Context,
+	request: &http::Request<hyper::Body>,
+	submitted_token: &str,
+) -> Result<()> {
+	let cookie_header = request
+		.headers()
+		.get(http::header::COOKIE)
+		.and_then(|value| value.to_str().ok())
+		.ok_or_else(|| err!("missing csrf cookie"))?;
+	let cookie_value = cookie_header
+		.split(';')
+		.map(|pair| pair.trim())
+		.find_map(|pair| pair.strip_prefix(&format!("{}=", CSRF_COOKIE_NAME)))
+		.ok_or_else(|| err!("missing csrf cookie"))?;
+	let (cookie_token, cookie_signature) = cookie_value
+		.split_once('.')
+		.ok_or_else(|| err!("malformed csrf cookie"))?;
+	if sign(&context.csrf_secret, cookie_token) != cookie_signature {
+		return Err(err!("invalid csrf cookie signature"));
+	}
+	if cookie_token != submitted_token {
+		return Err(err!("csrf token mismatch"));
+	}
+	Ok(())
+}
+
+fn sign(secret: &[u8], token: &str) -> String {
+	let mut mac = Hmac::<Sha256>::new_from_slice(secret).unwrap();
+	mac.update(token.as_bytes());
+	base64::encode(mac.finalize().into_bytes())
+}
@@ -0,0 +1,13 @@
+pub fn method_not_allowed() -> http::Response<hyper::Body> {
+	http::Response::builder()
+		.status(http::StatusCode::METHOD_NOT_ALLOWED)
+		.body(hyper::Body::from("method not allowed"))
+		.unwrap()
+}
+
+pub fn bad_request() -> http::Response<hyper::Body> {
+	http::Response::builder()
+		.status(http::StatusCode::BAD_REQUEST)
+		.body(hyper::Body::from("bad request"))
+		.unwrap()
+}
@@ -0,0 +1,48 @@
+use std::future::Future;
+use std::pin::Pin;
+
+pub mod csrf;
+pub mod error;
+
+/// Shared server state threaded through every page handler.
+pub struct Context {
+	pub database_pool: sqlx::AnyPool,
+	/// Which backend `database_pool` talks to. `sqlx::Any` does not rewrite
+	/// placeholder syntax, so handlers that build queries with placeholders
+	/// need this to pick `?` vs `$1, $2, ...` via `DatabaseDriver::placeholder`.
+	pub database_driver: DatabaseDriver,
+	/// HMAC key used to sign CSRF cookies. Generated once at startup; a
+	/// process restart invalidates outstanding tokens, which is fine since
+	/// they're also scoped to a single form render.
+	pub csrf_secret: Vec<u8>,
+}
+
+/// The backend a `sqlx::AnyPool` was connected to, inferred from the
+/// connection url's scheme by the CLI when it builds
+/// `tangram_app::options::DatabaseOptions`. Lives here, rather
+/// than on `tangram_app::options::DatabaseOptions` alone, because page
+/// handlers in `tangram_app` need it off `Context` to pick placeholder
+/// syntax at query time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DatabaseDriver {
+	Sqlite,
+	Postgres,
+	Mysql,
+}
+
+impl DatabaseDriver {
+	/// The `index`-th (1-based) positional placeholder for this backend.
+	/// SQLite and MySQL both accept positional `?`; Postgres requires
+	/// numbered `$1`, `$2`, ... and `sqlx::Any` does not translate between
+	/// them, so query strings must be built with this instead of a literal
+	/// `?`.
+	pub fn placeholder(self, index: usize) -> String {
+		match self {
+			DatabaseDriver::Sqlite | DatabaseDriver::Mysql => "?".to_owned(),
+			DatabaseDriver::Postgres => format!("${}", index),
+		}
+	}
+}
+
+pub type HandleOutput =
+	Pin<Box<dyn Future<Output = tangram_error::Result<http::Response<hyper::Body>>> + Send>>;
@@ -0,0 +1,15 @@
+use html::{component, html, Props};
+
+/// A hidden form field carrying the CSRF token minted for this session.
+/// Every `<ui::Form post?>` that mutates state should render one of these.
+#[derive(Props)]
+pub struct CsrfFieldProps {
+	pub token: String,
+}
+
+#[component]
+pub fn CsrfField(props: CsrfFieldProps) {
+	html! {
+		<input type="hidden" name="csrf_token" value={props.token} />
+	}
+}
@@ -0,0 +1,3 @@
+pub use csrf_field::{CsrfField, CsrfFieldProps};
+
+mod csrf_field;